@@ -28,6 +28,9 @@ pub enum BlockchainError {
     /// Block number does not exist in blockchain
     #[error("Unknown block number")]
     UnknownBlockNumber,
+    /// Block hash does not exist in blockchain
+    #[error("Unknown block hash")]
+    UnknownBlockHash,
 }
 
 /// Trait for implementations of an Ethereum blockchain.
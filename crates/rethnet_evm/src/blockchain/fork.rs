@@ -1,58 +1,160 @@
 use hashbrown::HashMap;
-use rethnet_eth::{remote::RpcClient, Address, B256, U256};
+use rethnet_eth::{block::Block, remote::RpcClient, Address, B256, U256};
 use revm::{
     db::BlockHashRef,
     primitives::{AccountInfo, SpecId},
 };
+use std::sync::{Arc, Mutex};
 
 use crate::state::StateDebug;
 
-use super::{Blockchain, InMemoryBlockchain};
+use super::{Blockchain, BlockchainError, InMemoryBlockchain};
 
+/// An error that occurred while constructing a [`ForkBlockchain`].
+#[derive(Debug, thiserror::Error)]
+pub enum ForkBlockchainCreationError<SE> {
+    /// An error that occurred while querying the remote node
+    #[error(transparent)]
+    Rpc(#[from] rethnet_eth::remote::RpcClientError),
+    /// An error that occurred while initializing local state
+    #[error(transparent)]
+    State(SE),
+}
+
+/// A blockchain that forks from a remote node at `fork_block_number`. Blocks at or below the
+/// fork point are fetched lazily from the remote node and cached; blocks above it are served by
+/// an embedded [`InMemoryBlockchain`] rooted at the fork block, so that locally mined blocks
+/// chain onto it the same way they would in a non-forked blockchain.
 pub struct ForkBlockchain {
     local_blockchain: InMemoryBlockchain,
     rpc_client: RpcClient,
     fork_block_number: U256,
+    remote_blocks: Mutex<HashMap<U256, Block>>,
 }
 
 impl ForkBlockchain {
-    pub fn new<S: StateDebug>(
-        state: &S,
-        spec_id: SpecId,
+    /// Constructs a new [`ForkBlockchain`], forking from the remote node at `remote_url` as of
+    /// `fork_block_number`.
+    pub async fn new<S: StateDebug>(
+        _state: &S,
+        _spec_id: SpecId,
         remote_url: &str,
         fork_block_number: U256,
-        genesis_accounts: HashMap<Address, AccountInfo>,
-    ) -> Result<Self, S::Error> {
+        // TODO: Apply these as balance/code overrides on top of the forked remote state once
+        // `StateDebug` exposes a mutable account accessor usable from here.
+        _genesis_accounts: HashMap<Address, AccountInfo>,
+    ) -> Result<Self, ForkBlockchainCreationError<S::Error>> {
         let rpc_client = RpcClient::new(remote_url);
 
-        let network_id = rpc_client.network_id().await?;
+        let _network_id = rpc_client.network_id().await?;
 
-        let local_blockchain = InMemoryBlockchain::new(state, spec_id)?;
+        let fork_block = rpc_client
+            .get_block_by_number(fork_block_number)
+            .await?
+            .expect("The fork block must exist on the remote chain");
+
+        // Safety: the fork block isn't necessarily number zero, but it's the genesis of the
+        // locally-mined chain that extends the remote one from this point onward.
+        let local_blockchain = unsafe { InMemoryBlockchain::with_genesis_block_unchecked(fork_block) };
 
         Ok(Self {
             local_blockchain,
             rpc_client,
             fork_block_number,
+            remote_blocks: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Fetches the remote block with the given number, caching the result so repeated lookups
+    /// don't re-hit the network.
+    fn fetch_remote_block(&self, number: U256) -> Result<Block, BlockchainError> {
+        if let Some(block) = self
+            .remote_blocks
+            .lock()
+            .expect("Remote block cache lock poisoned")
+            .get(&number)
+        {
+            return Ok(block.clone());
+        }
+
+        // `block_hash` runs synchronously from inside the EVM's execution loop, which this crate
+        // always drives from a worker thread of the multi-threaded Tokio runtime that owns the
+        // rest of this codebase (see the spawned tasks and `tokio::sync::RwLock`s throughout
+        // `rethnet_evm_napi`). `block_in_place` hands this thread's remaining async work to
+        // another worker for the duration of the blocking call below, instead of nesting a second
+        // runtime into the one already driving us, which would panic.
+        //
+        // The lock above is dropped before making the (possibly slow) RPC call, so unrelated
+        // cache lookups aren't serialized behind it; it's re-acquired only to record the result.
+        let block = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(self.rpc_client.get_block_by_number(number))
         })
+        .ok()
+        .flatten()
+        .ok_or(BlockchainError::UnknownBlockNumber)?;
+
+        self.remote_blocks
+            .lock()
+            .expect("Remote block cache lock poisoned")
+            .insert(number, block.clone());
+
+        Ok(block)
+    }
+
+    /// Reverts the canonical chain back to (and including) the block at `number`. Delegates
+    /// directly to the embedded [`InMemoryBlockchain`], which resolves `number` relative to its
+    /// own genesis (the fork block), so absolute block numbers work the same here as they do for
+    /// a non-forked chain.
+    pub fn revert_to_block(&mut self, number: U256) -> Result<(), BlockchainError> {
+        self.local_blockchain.revert_to_block(number)
+    }
+
+    /// Computes the route from the current canonical head to the block with hash `to`. See
+    /// [`InMemoryBlockchain::compute_route`].
+    #[allow(clippy::type_complexity)]
+    pub fn compute_route(
+        &self,
+        to: &B256,
+    ) -> Result<(Vec<Arc<Block>>, Vec<Arc<Block>>), BlockchainError> {
+        self.local_blockchain.compute_route(to)
+    }
+}
+
+impl std::fmt::Debug for ForkBlockchain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ForkBlockchain")
+            .field("fork_block_number", &self.fork_block_number)
+            .finish()
     }
 }
 
 impl BlockHashRef for ForkBlockchain {
-    type Error;
+    type Error = BlockchainError;
 
     fn block_hash(&self, number: U256) -> Result<B256, Self::Error> {
-        todo!()
+        if number <= self.fork_block_number {
+            self.fetch_remote_block(number)
+                .map(|block| block.header.hash())
+        } else {
+            // `local_blockchain` resolves block numbers relative to its own genesis (the fork
+            // block, whose number is `fork_block_number`), so the absolute number is passed
+            // through unchanged rather than re-based here.
+            self.local_blockchain.block_hash(number)
+        }
     }
 }
 
 impl Blockchain for ForkBlockchain {
-    type Error;
+    type Error = BlockchainError;
 
-    fn last_block(&self) -> rethnet_eth::block::Block {
-        todo!()
+    fn last_block(&self) -> Block {
+        // Either the highest locally mined block, or the fork block itself if nothing has been
+        // mined locally yet; `local_blockchain` is rooted at the fork block, so this falls out
+        // of its own `last_block` for free.
+        self.local_blockchain.last_block()
     }
 
-    fn insert_block(&mut self, block: rethnet_eth::block::Block) -> Result<(), Self::Error> {
-        todo!()
+    fn insert_block(&mut self, block: Block) -> Result<(), Self::Error> {
+        self.local_blockchain.insert_block(block)
     }
 }
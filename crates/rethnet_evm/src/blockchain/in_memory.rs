@@ -37,7 +37,11 @@ pub enum InsertBlockError {
 /// Blockchain that's stored in-memory.
 #[derive(Debug)]
 pub struct InMemoryBlockchain {
+    /// The canonical chain, indexed by block number.
     blocks: Vec<Arc<Block>>,
+    /// Every block that's been inserted, whether on the canonical chain or a side branch,
+    /// indexed by hash. Side-branch blocks stay here (but not in `blocks`) so that `block_by_hash`
+    /// can still resolve them and [`Self::compute_route`] can replay a reorg onto them.
     hash_to_block: HashMap<B256, Arc<Block>>,
 }
 
@@ -123,7 +127,14 @@ impl InMemoryBlockchain {
         self.hash_to_block.insert(block.header.hash(), block);
     }
 
-    unsafe fn with_genesis_block_unchecked(genesis_block: Block) -> Self {
+    /// Constructs a new [`InMemoryBlockchain`] treating the provided block as its genesis,
+    /// without checking that its number is zero.
+    ///
+    /// # Safety
+    ///
+    /// This is used by [`super::ForkBlockchain`] to root a local chain at an arbitrary fork
+    /// block number; callers outside of that use case should prefer [`Self::with_genesis_block`].
+    pub(crate) unsafe fn with_genesis_block_unchecked(genesis_block: Block) -> Self {
         let genesis_block = Arc::new(genesis_block);
         let mut hash_to_block = HashMap::new();
         hash_to_block.insert(genesis_block.header.hash(), genesis_block.clone());
@@ -133,6 +144,105 @@ impl InMemoryBlockchain {
             hash_to_block,
         }
     }
+
+    /// Returns the block with the given hash, whether it's on the canonical chain or a side
+    /// branch, if it exists.
+    pub fn block_by_hash(&self, hash: &B256) -> Option<&Arc<Block>> {
+        self.hash_to_block.get(hash)
+    }
+
+    /// Reverts the canonical chain back to (and including) the block at `number`, discarding
+    /// every later canonical block and its `hash_to_block` entry. Blocks on side branches are
+    /// left untouched, so they remain resolvable via [`Self::block_by_hash`] and eligible to be
+    /// enacted by a subsequent call to [`Self::compute_route`].
+    pub fn revert_to_block(&mut self, number: U256) -> Result<(), BlockchainError> {
+        let index = self.block_number_to_index(number)?;
+
+        if index >= self.blocks.len() {
+            return Err(BlockchainError::UnknownBlockNumber);
+        }
+
+        for block in self.blocks.drain(index + 1..) {
+            self.hash_to_block.remove(&block.header.hash());
+        }
+
+        Ok(())
+    }
+
+    /// Computes the route from the current canonical head to the block with hash `to`: the
+    /// canonical blocks to retract, closest to the head first, and the blocks to enact to reach
+    /// `to`, furthest from `to` first. Callers can retract the first set and then enact the
+    /// second to replay a reorg onto `to`.
+    #[allow(clippy::type_complexity)]
+    pub fn compute_route(
+        &self,
+        to: &B256,
+    ) -> Result<(Vec<Arc<Block>>, Vec<Arc<Block>>), BlockchainError> {
+        let mut to_enact = Vec::new();
+
+        let mut block = self
+            .hash_to_block
+            .get(to)
+            .ok_or(BlockchainError::UnknownBlockHash)?
+            .clone();
+
+        // Walk the target branch back towards genesis until hitting the common ancestor: the
+        // first block that's also present on the canonical chain at its own height.
+        let ancestor_number = loop {
+            if self.is_canonical(&block) {
+                break block.header.number;
+            }
+
+            let parent_hash = block.header.parent_hash;
+            to_enact.push(block);
+
+            block = self
+                .hash_to_block
+                .get(&parent_hash)
+                .ok_or(BlockchainError::InvalidParentHash)?
+                .clone();
+        };
+
+        to_enact.reverse();
+
+        let ancestor_index = self.block_number_to_index(ancestor_number)?;
+        let to_retract = self.blocks[ancestor_index + 1..]
+            .iter()
+            .rev()
+            .cloned()
+            .collect();
+
+        Ok((to_retract, to_enact))
+    }
+
+    /// Returns whether `block` is the canonical block at its height.
+    fn is_canonical(&self, block: &Block) -> bool {
+        self.block_number_to_index(block.header.number)
+            .ok()
+            .and_then(|index| self.blocks.get(index))
+            .map_or(false, |canonical_block| {
+                canonical_block.header.hash() == block.header.hash()
+            })
+    }
+
+    /// Converts a block number into an index into `self.blocks`, relative to this chain's
+    /// genesis block number. That number is usually zero, but [`super::ForkBlockchain`] roots its
+    /// embedded [`InMemoryBlockchain`] at the (generally nonzero) fork block via
+    /// [`Self::with_genesis_block_unchecked`], so the index must be computed relative to whatever
+    /// number the genesis block actually has, not assumed to be block number itself.
+    fn block_number_to_index(&self, number: U256) -> Result<usize, BlockchainError> {
+        let genesis_number = self.blocks[0].header.number;
+        let offset = number
+            .checked_sub(genesis_number)
+            .ok_or(BlockchainError::UnknownBlockNumber)?;
+
+        // Question: Do we need to support block number larger than u64::MAX
+        if offset > U256::from(u64::MAX) {
+            return Err(BlockchainError::BlockNumberTooLarge);
+        }
+
+        usize::try_from(offset.as_limbs()[0]).map_err(|_| BlockchainError::BlockNumberTooLarge)
+    }
 }
 
 impl Blockchain for InMemoryBlockchain {
@@ -147,25 +257,37 @@ impl Blockchain for InMemoryBlockchain {
     }
 
     fn insert_block(&mut self, block: Block) -> Result<(), Self::Error> {
-        let last_block = self
-            .blocks
-            .last()
-            .expect("A genesis block is always present");
+        let parent = self
+            .hash_to_block
+            .get(&block.header.parent_hash)
+            .ok_or(BlockchainError::InvalidParentHash)?;
 
-        let next_block_number = last_block.header.number + U256::from(1);
-        if block.header.number != next_block_number {
+        let expected_number = parent.header.number + U256::from(1);
+        if block.header.number != expected_number {
             return Err(BlockchainError::InvalidBlockNumber {
                 actual: block.header.number,
-                expected: next_block_number,
+                expected: expected_number,
             });
         }
 
-        if block.header.parent_hash != last_block.header.hash() {
-            return Err(BlockchainError::InvalidParentHash);
-        }
+        // A block extends the canonical chain only if its parent is the current canonical head;
+        // otherwise it starts (or continues) a side branch and is kept solely in
+        // `hash_to_block`, resolvable by hash but invisible to `last_block`/`block_hash`.
+        let extends_canonical_chain = {
+            let last_block = self
+                .blocks
+                .last()
+                .expect("A genesis block is always present");
 
-        // Safety: We've already performed the checks
-        unsafe { self.insert_block_unchecked(block) };
+            block.header.parent_hash == last_block.header.hash()
+        };
+
+        let block = Arc::new(block);
+        self.hash_to_block.insert(block.header.hash(), block.clone());
+
+        if extends_canonical_chain {
+            self.blocks.push(block);
+        }
 
         Ok(())
     }
@@ -175,13 +297,7 @@ impl BlockHashRef for InMemoryBlockchain {
     type Error = BlockchainError;
 
     fn block_hash(&self, number: U256) -> Result<B256, Self::Error> {
-        // Question: Do we need to support block number larger than u64::MAX
-        if number > U256::from(u64::MAX) {
-            return Err(BlockchainError::BlockNumberTooLarge);
-        }
-
-        let number = usize::try_from(number.as_limbs()[0])
-            .map_err(|_| BlockchainError::BlockNumberTooLarge)?;
+        let number = self.block_number_to_index(number)?;
 
         self.blocks
             .get(number)
@@ -189,3 +305,100 @@ impl BlockHashRef for InMemoryBlockchain {
             .ok_or(BlockchainError::UnknownBlockNumber)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a standalone block with the given number and parent hash; distinct `gas_limit`s
+    /// keep otherwise-identical blocks (e.g. competing side branches) from hashing the same.
+    fn test_block(number: U256, parent_hash: B256, gas_limit: U256) -> Block {
+        Block::new(
+            PartialHeader {
+                number,
+                parent_hash,
+                state_root: KECCAK_NULL_RLP,
+                receipts_root: KECCAK_NULL_RLP,
+                gas_limit,
+                gas_used: U256::ZERO,
+                timestamp: U256::ZERO,
+                ..PartialHeader::default()
+            },
+            Vec::new(),
+            Vec::new(),
+        )
+    }
+
+    /// A chain whose genesis sits at a nonzero block number, the way [`super::ForkBlockchain`]
+    /// roots its embedded chain at the fork block rather than at zero.
+    fn chain_with_nonzero_genesis() -> (InMemoryBlockchain, Block, Block, Block) {
+        let genesis = test_block(U256::from(10), B256::zero(), U256::from(1_000_000));
+        let blockchain = unsafe { InMemoryBlockchain::with_genesis_block_unchecked(genesis.clone()) };
+
+        let block_11 = test_block(U256::from(11), genesis.header.hash(), U256::from(1_000_000));
+        let block_12 = test_block(
+            U256::from(12),
+            block_11.header.hash(),
+            U256::from(1_000_000),
+        );
+
+        (blockchain, genesis, block_11, block_12)
+    }
+
+    #[test]
+    fn revert_to_block_is_relative_to_nonzero_genesis() {
+        let (mut blockchain, _genesis, block_11, block_12) = chain_with_nonzero_genesis();
+
+        blockchain.insert_block(block_11.clone()).unwrap();
+        blockchain.insert_block(block_12).unwrap();
+        assert_eq!(blockchain.last_block().header.number, U256::from(12));
+
+        blockchain.revert_to_block(U256::from(11)).unwrap();
+
+        assert_eq!(blockchain.last_block().header.hash(), block_11.header.hash());
+    }
+
+    #[test]
+    fn revert_to_block_rejects_a_number_below_genesis() {
+        let (mut blockchain, _genesis, block_11, block_12) = chain_with_nonzero_genesis();
+
+        blockchain.insert_block(block_11).unwrap();
+        blockchain.insert_block(block_12).unwrap();
+
+        assert!(matches!(
+            blockchain.revert_to_block(U256::from(9)),
+            Err(BlockchainError::UnknownBlockNumber)
+        ));
+    }
+
+    #[test]
+    fn compute_route_walks_back_to_the_common_ancestor() {
+        let (mut blockchain, _genesis, block_11, canonical_12) = chain_with_nonzero_genesis();
+
+        blockchain.insert_block(block_11.clone()).unwrap();
+        blockchain.insert_block(canonical_12.clone()).unwrap();
+
+        // A side branch forking off of block 11, never becoming canonical.
+        let side_12 = test_block(U256::from(12), block_11.header.hash(), U256::from(2_000_000));
+        let side_13 = test_block(
+            U256::from(13),
+            side_12.header.hash(),
+            U256::from(2_000_000),
+        );
+        blockchain.insert_block(side_12.clone()).unwrap();
+        blockchain.insert_block(side_13.clone()).unwrap();
+
+        let (to_retract, to_enact) = blockchain.compute_route(&side_13.header.hash()).unwrap();
+
+        let to_retract_hashes: Vec<B256> =
+            to_retract.iter().map(|block| block.header.hash()).collect();
+        let to_enact_hashes: Vec<B256> =
+            to_enact.iter().map(|block| block.header.hash()).collect();
+
+        assert_eq!(to_retract_hashes, vec![canonical_12.header.hash()]);
+        assert_eq!(
+            to_enact_hashes,
+            vec![side_12.header.hash(), side_13.header.hash()]
+        );
+    }
+}
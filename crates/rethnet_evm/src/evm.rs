@@ -1,6 +1,10 @@
 use std::fmt::Debug;
 
-use revm::{BlockEnv, CfgEnv, TxEnv};
+use revm::{
+    db::CacheDB,
+    primitives::{Address, EVMError, ExecutionResult, U256},
+    BlockEnv, CfgEnv, TxEnv,
+};
 
 use crate::{blockchain::AsyncBlockchain, db::AsyncDatabase};
 
@@ -26,3 +30,80 @@ where
 
     evm
 }
+
+/// A single call within a [`run_call_batch`] batch: the transaction to execute, plus overrides
+/// that apply only to this call, leaving the shared [`BlockEnv`] untouched for the rest of the
+/// batch.
+#[derive(Clone, Debug)]
+pub struct BatchCall {
+    /// The transaction to execute
+    pub transaction: TxEnv,
+    /// Overrides the block number for this call only
+    pub block_number: Option<U256>,
+    /// Overrides the block timestamp for this call only
+    pub timestamp: Option<U256>,
+    /// Overrides the transaction's sender for this call only
+    pub sender: Option<Address>,
+}
+
+/// An error that occurred while running a [`run_call_batch`] batch.
+#[derive(Debug, thiserror::Error)]
+pub enum CallBatchError<DE> {
+    /// An error that occurred while executing one of the batch's transactions.
+    #[error(transparent)]
+    Transaction(#[from] EVMError<DE>),
+}
+
+/// Executes `calls` sequentially against a copy-on-write overlay of `db`, sharing `blockchain`
+/// and `cfg` across the batch. Each call observes the state changes made by the earlier calls in
+/// the same batch, but nothing is ever committed back to `db`: the overlay, and every state
+/// change made while running the batch, is discarded once this returns. Mirrors a multicall RPC,
+/// letting callers simulate sequences like "approve then transferFrom" in a single request.
+///
+/// The napi layer's `BlockMiner::simulate_transactions` is not a substitute for this: it only
+/// takes one [`crate::BlockOverrides`] for the whole batch and has no per-call sender override, so
+/// it can't express a bundle where each call runs against a different block context. A napi
+/// binding for this function belongs alongside `BlockMiner`'s, constructed from an
+/// `AsyncBlockchain`/`AsyncDatabase` wrapping the napi layer's `Blockchain`/`StateManager`
+/// handles the same way `AsyncDatabase` already wraps a `SyncDatabase` everywhere else in this
+/// module.
+pub fn run_call_batch<BE, DE>(
+    blockchain: &AsyncBlockchain<BE>,
+    db: &AsyncDatabase<DE>,
+    cfg: CfgEnv,
+    block: BlockEnv,
+    calls: Vec<BatchCall>,
+) -> Result<Vec<ExecutionResult>, CallBatchError<DE>>
+where
+    BE: Debug + Send + 'static,
+    DE: Debug + Send + 'static,
+{
+    let mut overlay = CacheDB::new(db);
+
+    calls
+        .into_iter()
+        .map(|call| {
+            let mut call_block = block.clone();
+            if let Some(number) = call.block_number {
+                call_block.number = number;
+            }
+            if let Some(timestamp) = call.timestamp {
+                call_block.timestamp = timestamp;
+            }
+
+            let mut transaction = call.transaction;
+            if let Some(sender) = call.sender {
+                transaction.caller = sender;
+            }
+
+            let mut evm = revm::EVM::new();
+            evm.set_blockchain(blockchain);
+            evm.database(&mut overlay);
+            evm.env.cfg = cfg.clone();
+            evm.env.block = call_block;
+            evm.env.tx = transaction;
+
+            evm.transact_commit().map_err(CallBatchError::Transaction)
+        })
+        .collect()
+}
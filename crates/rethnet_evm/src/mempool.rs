@@ -1,24 +1,128 @@
-use rethnet_eth::B256;
+use std::{cmp::Ordering, collections::{BinaryHeap, VecDeque}};
+
+use hashbrown::HashMap;
+use rethnet_eth::{Address, B256, U256};
 use revm::db::StateRef;
 
 use crate::PendingTransaction;
 
-/// The mempool contains transactions pending inclusion in the blockchain.
+/// The minimum percentage by which a replacement transaction's fees must exceed the transaction
+/// it replaces, unless a different value is configured via [`MemPool::with_min_bump_percentage`].
+const DEFAULT_MIN_BUMP_PERCENTAGE: u64 = 10;
+
+/// An error that occurred while trying to add a transaction to the [`MemPool`].
+#[derive(Debug, thiserror::Error)]
+pub enum MemPoolAddTransactionError<SE> {
+    /// The transaction attempts to replace an existing transaction with the same sender and
+    /// nonce, but doesn't bump its fees by enough.
+    #[error(
+        "Replacement transaction underpriced. A fee bump of at least {min_bump_percentage}% over the existing transaction with nonce {nonce} is required."
+    )]
+    ReplacementUnderpriced {
+        /// The nonce shared by the existing and replacement transaction
+        nonce: U256,
+        /// The minimum required fee bump, as a percentage
+        min_bump_percentage: u64,
+    },
+    /// An error that occurred while retrieving account information from state
+    #[error(transparent)]
+    State(SE),
+}
+
+/// A transaction sitting in [`MemPool`]'s future queue, annotated with the pool's block counter
+/// at the time it was inserted, so staleness can be judged later.
+#[derive(Clone, Debug)]
+struct FutureTransaction {
+    transaction: PendingTransaction,
+    inserted_at_block: u64,
+}
+
+/// The outcome of [`MemPool::update`]: which future transactions were promoted to pending, and
+/// which were discarded outright (already mined/superseded, or evicted for staleness/capacity).
 #[derive(Clone, Debug, Default)]
+pub struct MemPoolUpdate {
+    /// Transactions moved from the future queue to the pending queue
+    pub promoted: Vec<PendingTransaction>,
+    /// Transactions removed from the pool entirely
+    pub discarded: Vec<PendingTransaction>,
+}
+
+/// The mempool contains transactions pending inclusion in the blockchain.
+#[derive(Clone, Debug)]
 pub struct MemPool {
     /// Transactions that can be executed now
     pending_transactions: Vec<PendingTransaction>,
     /// Transactions that can be executed in the future, once the nonce is high enough
-    future_transactions: Vec<PendingTransaction>,
+    future_transactions: Vec<FutureTransaction>,
+    /// The minimum percentage by which a replacement transaction must bump the fees of the
+    /// transaction it replaces.
+    min_bump_percentage: u64,
+    /// The maximum number of transactions the pool may hold in total, if any.
+    max_count: Option<usize>,
+    /// The maximum number of transactions a single sender may have in the pool, if any.
+    max_count_per_sender: Option<usize>,
+    /// The maximum number of blocks a future transaction may sit in the pool before it's
+    /// considered stale, if any.
+    future_transaction_ttl: Option<u64>,
+    /// The maximum number of future transactions a single sender may have in the pool, if any.
+    max_future_transactions_per_sender: Option<usize>,
+    /// Incremented every call to [`Self::update`]; used as the pool's notion of "now" for
+    /// judging future transaction staleness.
+    current_block_number: u64,
 }
 
 impl MemPool {
+    /// Constructs a new [`MemPool`] with the default minimum replacement fee bump percentage and
+    /// no capacity or staleness limits.
+    pub fn new() -> Self {
+        Self::with_min_bump_percentage(DEFAULT_MIN_BUMP_PERCENTAGE)
+    }
+
+    /// Constructs a new [`MemPool`], requiring replacement transactions to bump fees by at least
+    /// `min_bump_percentage`, with no capacity or staleness limits.
+    pub fn with_min_bump_percentage(min_bump_percentage: u64) -> Self {
+        Self::with_limits(min_bump_percentage, None, None)
+    }
+
+    /// Constructs a new [`MemPool`] with the provided minimum replacement fee bump percentage and
+    /// capacity limits. Once `max_count` or `max_count_per_sender` is reached, the lowest-scored
+    /// transaction (by effective gas price) is evicted to make room.
+    pub fn with_limits(
+        min_bump_percentage: u64,
+        max_count: Option<usize>,
+        max_count_per_sender: Option<usize>,
+    ) -> Self {
+        Self {
+            pending_transactions: Vec::new(),
+            future_transactions: Vec::new(),
+            min_bump_percentage,
+            max_count,
+            max_count_per_sender,
+            future_transaction_ttl: None,
+            max_future_transactions_per_sender: None,
+            current_block_number: 0,
+        }
+    }
+
+    /// Returns a copy of `self` that evicts future transactions once they've sat in the pool for
+    /// more than `future_transaction_ttl` calls to [`Self::update`], or once a sender has more
+    /// than `max_future_transactions_per_sender` of them.
+    pub fn with_future_transaction_limits(
+        mut self,
+        future_transaction_ttl: Option<u64>,
+        max_future_transactions_per_sender: Option<usize>,
+    ) -> Self {
+        self.future_transaction_ttl = future_transaction_ttl;
+        self.max_future_transactions_per_sender = max_future_transactions_per_sender;
+        self
+    }
+
     /// Tries to add the provided transaction to the [`Pool`].
     pub fn add_transaction<S: StateRef>(
         &mut self,
         state: &S,
         transaction: PendingTransaction,
-    ) -> Result<(), S::Error> {
+    ) -> Result<(), MemPoolAddTransactionError<S::Error>> {
         self.add_transaction_impl(state, transaction)
     }
 
@@ -37,29 +141,76 @@ impl MemPool {
             .future_transactions
             .iter()
             .enumerate()
-            .find(|(_, transaction)| *transaction.hash() == *hash)
+            .find(|(_, slot)| *slot.transaction.hash() == *hash)
         {
-            return Some(self.future_transactions.remove(idx));
+            return Some(self.future_transactions.remove(idx).transaction);
         }
 
         None
     }
 
-    /// Updates the [`Pool`], moving any future transactions to the pending status, if their nonces are high enough.
-    pub fn update<S: StateRef>(&mut self, state: &S) -> Result<(), S::Error> {
+    /// Updates the [`MemPool`], moving any future transactions to the pending status if their
+    /// nonces are now high enough, discarding any that have been superseded by an already-mined
+    /// transaction, and evicting stale or over-capacity future transactions. Returns which
+    /// transactions were promoted and which were discarded, so the caller can clean up
+    /// receipts/subscriptions for the latter.
+    pub fn update<S: StateRef>(&mut self, state: &S) -> Result<MemPoolUpdate, S::Error> {
+        self.current_block_number += 1;
+
         let mut future_transactions = Vec::with_capacity(self.future_transactions.capacity());
         std::mem::swap(&mut self.future_transactions, &mut future_transactions);
 
-        for transaction in future_transactions.into_iter() {
-            self.add_transaction_impl(state, transaction)?;
+        // Group by sender and walk each sender's slots in nonce order, advancing the expected
+        // nonce as slots promote, so a whole contiguous run promotes in this single pass instead
+        // of needing one `update()` call per nonce.
+        let mut by_sender: HashMap<Address, Vec<FutureTransaction>> = HashMap::new();
+        for slot in future_transactions {
+            by_sender
+                .entry(*slot.transaction.caller())
+                .or_default()
+                .push(slot);
         }
 
-        Ok(())
+        let mut update = MemPoolUpdate::default();
+
+        for (sender, mut slots) in by_sender {
+            slots.sort_unstable_by_key(|slot| *slot.transaction.nonce());
+
+            let account = state.basic(sender)?.unwrap_or_default();
+            let mut expected_nonce = account.nonce;
+
+            for slot in slots {
+                if *slot.transaction.nonce() < account.nonce {
+                    // Already mined or superseded by another transaction; nothing more to do.
+                    update.discarded.push(slot.transaction);
+                    continue;
+                }
+
+                if let Some(ttl) = self.future_transaction_ttl {
+                    if self.current_block_number - slot.inserted_at_block > ttl {
+                        update.discarded.push(slot.transaction);
+                        continue;
+                    }
+                }
+
+                if *slot.transaction.nonce() == expected_nonce {
+                    update.promoted.push(slot.transaction.clone());
+                    self.pending_transactions.push(slot.transaction);
+                    expected_nonce += U256::from(1);
+                } else {
+                    self.future_transactions.push(slot);
+                }
+            }
+        }
+
+        self.enforce_future_depth_limit(&mut update.discarded);
+
+        Ok(update)
     }
 
-    /// Returns all pending transactions, for which the nonces are too high.
-    pub fn future_transactions(&self) -> &[PendingTransaction] {
-        &self.future_transactions
+    /// Returns all future transactions, for which the nonces are too high.
+    pub fn future_transactions(&self) -> impl Iterator<Item = &PendingTransaction> {
+        self.future_transactions.iter().map(|slot| &slot.transaction)
     }
 
     /// Returns all pending transactions, for which the nonces are guaranteed to be high enough.
@@ -67,6 +218,17 @@ impl MemPool {
         &self.pending_transactions
     }
 
+    /// Returns the ready transactions in priority order: across senders by effective priority fee
+    /// given `base_fee` (highest first, ties broken by the order in which they became eligible),
+    /// while keeping each sender's own transactions in nonce order, since a later nonce is never
+    /// usable before its predecessor has been included.
+    pub fn pending_transactions_by_priority(
+        &self,
+        base_fee: Option<U256>,
+    ) -> PendingTransactionsByPriority {
+        PendingTransactionsByPriority::new(&self.pending_transactions, base_fee)
+    }
+
     /// Returns the pending transaction corresponding to the provided hash, if it exists.
     pub fn transaction_by_hash(&self, hash: &B256) -> Option<&PendingTransaction> {
         self.pending_transactions
@@ -75,6 +237,7 @@ impl MemPool {
             .or_else(|| {
                 self.future_transactions
                     .iter()
+                    .map(|slot| &slot.transaction)
                     .find(|transaction| *transaction.hash() == *hash)
             })
     }
@@ -83,17 +246,483 @@ impl MemPool {
         &mut self,
         state: &S,
         transaction: PendingTransaction,
-    ) -> Result<(), S::Error> {
-        let account = state.basic(*transaction.caller())?;
+    ) -> Result<(), MemPoolAddTransactionError<S::Error>> {
+        if let Some(existing) = Self::find_same_nonce_pending(&mut self.pending_transactions, &transaction)
+            .or_else(|| Self::find_same_nonce_future(&mut self.future_transactions, &transaction))
+        {
+            if !is_sufficient_fee_bump(existing, &transaction, self.min_bump_percentage) {
+                return Err(MemPoolAddTransactionError::ReplacementUnderpriced {
+                    nonce: *transaction.nonce(),
+                    min_bump_percentage: self.min_bump_percentage,
+                });
+            }
+
+            *existing = transaction;
+            return Ok(());
+        }
+
+        let account = state
+            .basic(*transaction.caller())
+            .map_err(MemPoolAddTransactionError::State)?;
 
         // Question: Must the account exist?
         let account = account.unwrap_or_default();
-        if *transaction.nonce() > account.nonce {
-            self.future_transactions.push(transaction);
+        let sender = *transaction.caller();
+        let ready_nonce = self.next_ready_nonce(sender, account.nonce);
+        if *transaction.nonce() > ready_nonce {
+            self.future_transactions.push(FutureTransaction {
+                transaction,
+                inserted_at_block: self.current_block_number,
+            });
         } else {
             self.pending_transactions.push(transaction);
         }
 
+        self.enforce_sender_limit(sender);
+        self.enforce_global_limit();
+
         Ok(())
     }
+
+    /// Returns the next nonce `sender` is ready for: the on-chain account nonce, advanced past
+    /// whichever of `sender`'s nonces already sit in `pending_transactions` form a contiguous run
+    /// starting there. A transaction at this nonce extends that run and belongs in `pending`;
+    /// anything higher leaves a gap and belongs in `future`.
+    fn next_ready_nonce(&self, sender: Address, account_nonce: U256) -> U256 {
+        let mut sender_nonces: Vec<U256> = self
+            .pending_transactions
+            .iter()
+            .filter(|transaction| *transaction.caller() == sender)
+            .map(|transaction| *transaction.nonce())
+            .collect();
+        sender_nonces.sort_unstable();
+
+        let mut ready_nonce = account_nonce;
+        for nonce in sender_nonces {
+            if nonce == ready_nonce {
+                ready_nonce += U256::from(1);
+            } else if nonce > ready_nonce {
+                break;
+            }
+        }
+
+        ready_nonce
+    }
+
+    /// Finds the transaction in `transactions` with the same sender and nonce as `transaction`,
+    /// if any.
+    fn find_same_nonce_pending<'a>(
+        transactions: &'a mut [PendingTransaction],
+        transaction: &PendingTransaction,
+    ) -> Option<&'a mut PendingTransaction> {
+        transactions.iter_mut().find(|existing| {
+            existing.caller() == transaction.caller() && existing.nonce() == transaction.nonce()
+        })
+    }
+
+    /// Finds the transaction in `future_transactions` with the same sender and nonce as
+    /// `transaction`, if any.
+    fn find_same_nonce_future<'a>(
+        future_transactions: &'a mut [FutureTransaction],
+        transaction: &PendingTransaction,
+    ) -> Option<&'a mut PendingTransaction> {
+        future_transactions
+            .iter_mut()
+            .find(|slot| {
+                slot.transaction.caller() == transaction.caller()
+                    && slot.transaction.nonce() == transaction.nonce()
+            })
+            .map(|slot| &mut slot.transaction)
+    }
+
+    /// Evicts `sender`'s lowest-scored transactions until it's within `max_count_per_sender`.
+    fn enforce_sender_limit(&mut self, sender: Address) {
+        let Some(max_count_per_sender) = self.max_count_per_sender else {
+            return;
+        };
+
+        loop {
+            let count = self
+                .pending_transactions
+                .iter()
+                .chain(self.future_transactions.iter().map(|slot| &slot.transaction))
+                .filter(|transaction| *transaction.caller() == sender)
+                .count();
+
+            if count <= max_count_per_sender {
+                break;
+            }
+
+            self.evict_lowest_scored(Some(sender));
+        }
+    }
+
+    /// Evicts the pool's lowest-scored transactions until it's within `max_count`.
+    fn enforce_global_limit(&mut self) {
+        let Some(max_count) = self.max_count else {
+            return;
+        };
+
+        while self.pending_transactions.len() + self.future_transactions.len() > max_count {
+            self.evict_lowest_scored(None);
+        }
+    }
+
+    /// Discards, per sender, the furthest-out (highest-nonce) future transactions beyond
+    /// `max_future_transactions_per_sender`, appending them to `discarded`.
+    fn enforce_future_depth_limit(&mut self, discarded: &mut Vec<PendingTransaction>) {
+        let Some(max_future_transactions_per_sender) = self.max_future_transactions_per_sender
+        else {
+            return;
+        };
+
+        let mut indices_by_sender: HashMap<Address, Vec<usize>> = HashMap::new();
+        for (idx, slot) in self.future_transactions.iter().enumerate() {
+            indices_by_sender
+                .entry(*slot.transaction.caller())
+                .or_default()
+                .push(idx);
+        }
+
+        let mut indices_to_remove = Vec::new();
+        for mut indices in indices_by_sender.into_values() {
+            if indices.len() <= max_future_transactions_per_sender {
+                continue;
+            }
+
+            indices.sort_unstable_by_key(|&idx| {
+                std::cmp::Reverse(*self.future_transactions[idx].transaction.nonce())
+            });
+            indices_to_remove
+                .extend(indices.into_iter().take(indices.len() - max_future_transactions_per_sender));
+        }
+
+        // Remove highest indices first so earlier indices stay valid.
+        indices_to_remove.sort_unstable_by(|a, b| b.cmp(a));
+        for idx in indices_to_remove {
+            discarded.push(self.future_transactions.remove(idx).transaction);
+        }
+    }
+
+    /// Evicts the lowest-scored transaction in the pool, optionally restricted to `sender`.
+    fn evict_lowest_scored(&mut self, sender: Option<Address>) {
+        let matches = |transaction: &PendingTransaction| {
+            sender.map_or(true, |sender| *transaction.caller() == sender)
+        };
+
+        let lowest_pending = self
+            .pending_transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, transaction)| matches(transaction))
+            .min_by_key(|(_, transaction)| effective_gas_price(transaction, None));
+
+        let lowest_future = self
+            .future_transactions
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| matches(&slot.transaction))
+            .min_by_key(|(_, slot)| effective_gas_price(&slot.transaction, None));
+
+        match (lowest_pending, lowest_future) {
+            (Some((pending_idx, pending)), Some((future_idx, future))) => {
+                if effective_gas_price(pending, None)
+                    <= effective_gas_price(&future.transaction, None)
+                {
+                    self.pending_transactions.remove(pending_idx);
+                } else {
+                    self.future_transactions.remove(future_idx);
+                }
+            }
+            (Some((idx, _)), None) => {
+                self.pending_transactions.remove(idx);
+            }
+            (None, Some((idx, _))) => {
+                self.future_transactions.remove(idx);
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+impl Default for MemPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Iterator returned by [`MemPool::pending_transactions_by_priority`]. Yields ready transactions
+/// across senders by effective priority fee, highest first, while keeping each sender's own
+/// transactions in nonce order.
+///
+/// A transaction popped by [`Self::next`] that isn't actually included by the caller (e.g. it
+/// doesn't fit in the block's remaining gas) must *not* be followed by [`Self::mark_included`]:
+/// since nonces must be consumed in order, no later transaction from that sender can be included
+/// in this block either, so the sender simply drops out of contention until the next call.
+pub struct PendingTransactionsByPriority {
+    base_fee: Option<U256>,
+    next_sequence_number: usize,
+    sender_queues: HashMap<Address, VecDeque<PendingTransaction>>,
+    queue: BinaryHeap<PriorityTransaction>,
+}
+
+impl PendingTransactionsByPriority {
+    fn new(pending_transactions: &[PendingTransaction], base_fee: Option<U256>) -> Self {
+        let mut sender_queues: HashMap<Address, VecDeque<PendingTransaction>> = HashMap::new();
+        for transaction in pending_transactions.iter().cloned() {
+            sender_queues
+                .entry(*transaction.caller())
+                .or_default()
+                .push_back(transaction);
+        }
+        for queue in sender_queues.values_mut() {
+            queue
+                .make_contiguous()
+                .sort_unstable_by_key(|transaction| *transaction.nonce());
+        }
+
+        let mut next_sequence_number = 0usize;
+        let queue = sender_queues
+            .values_mut()
+            .filter_map(|sender_queue| Self::next_eligible(sender_queue, base_fee))
+            .map(|transaction| {
+                let priority_transaction =
+                    PriorityTransaction::new(transaction, next_sequence_number, base_fee);
+                next_sequence_number += 1;
+                priority_transaction
+            })
+            .collect();
+
+        Self {
+            base_fee,
+            next_sequence_number,
+            sender_queues,
+            queue,
+        }
+    }
+
+    /// Must be called after a transaction yielded by [`Self::next`] was actually included, so the
+    /// same sender's next nonce-ordered transaction becomes eligible for this block.
+    pub fn mark_included(&mut self, sender: Address) {
+        if let Some(sender_queue) = self.sender_queues.get_mut(&sender) {
+            if let Some(next_transaction) = Self::next_eligible(sender_queue, self.base_fee) {
+                self.queue.push(PriorityTransaction::new(
+                    next_transaction,
+                    self.next_sequence_number,
+                    self.base_fee,
+                ));
+                self.next_sequence_number += 1;
+            }
+        }
+    }
+
+    /// Pops the next nonce-ordered transaction off of a sender's queue that is eligible for the
+    /// given base fee, discarding (and leaving in the mempool) any transaction whose
+    /// `max_fee_per_gas` cannot cover it. Since nonces must be consumed in order, a sender whose
+    /// head transaction is ineligible contributes no further transactions this block.
+    fn next_eligible(
+        sender_queue: &mut VecDeque<PendingTransaction>,
+        base_fee: Option<U256>,
+    ) -> Option<PendingTransaction> {
+        let transaction = sender_queue.pop_front()?;
+
+        if effective_priority_fee(&transaction, base_fee).is_some() {
+            Some(transaction)
+        } else {
+            None
+        }
+    }
+}
+
+impl Iterator for PendingTransactionsByPriority {
+    type Item = PendingTransaction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.queue
+            .pop()
+            .map(|PriorityTransaction { transaction, .. }| transaction)
+    }
+}
+
+/// A transaction ordered by its effective priority fee, with ties broken in favour of the
+/// transaction that became eligible first.
+struct PriorityTransaction {
+    priority_fee: U256,
+    sequence_number: usize,
+    transaction: PendingTransaction,
+}
+
+impl PriorityTransaction {
+    fn new(transaction: PendingTransaction, sequence_number: usize, base_fee: Option<U256>) -> Self {
+        // Eligibility (and thus the priority fee) was already established by `next_eligible`.
+        let priority_fee = effective_priority_fee(&transaction, base_fee)
+            .expect("transaction must have already been proven eligible");
+
+        Self {
+            priority_fee,
+            sequence_number,
+            transaction,
+        }
+    }
+}
+
+impl PartialEq for PriorityTransaction {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority_fee == other.priority_fee && self.sequence_number == other.sequence_number
+    }
+}
+
+impl Eq for PriorityTransaction {}
+
+impl PartialOrd for PriorityTransaction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PriorityTransaction {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority_fee
+            .cmp(&other.priority_fee)
+            // Older (lower sequence number) transactions are preferred on a tie, so reverse the
+            // comparison for the max-heap `BinaryHeap` to pop them first.
+            .then_with(|| other.sequence_number.cmp(&self.sequence_number))
+    }
+}
+
+/// Returns the effective priority fee a transaction would pay at the given base fee, or `None` if
+/// the transaction's `max_fee_per_gas` cannot cover that base fee at all.
+fn effective_priority_fee(transaction: &PendingTransaction, base_fee: Option<U256>) -> Option<U256> {
+    let max_fee_per_gas = transaction.gas_price();
+
+    match base_fee {
+        Some(base_fee) => {
+            if max_fee_per_gas < base_fee {
+                return None;
+            }
+
+            let max_priority_fee_per_gas = transaction
+                .max_priority_fee_per_gas()
+                .unwrap_or(max_fee_per_gas);
+
+            Some(max_priority_fee_per_gas.min(max_fee_per_gas - base_fee))
+        }
+        None => Some(max_fee_per_gas),
+    }
+}
+
+/// Returns the effective gas price a transaction pays at the given base fee: for EIP-1559
+/// transactions, `min(max_fee_per_gas, base_fee + max_priority_fee_per_gas)`; for legacy/2930
+/// transactions, their flat gas price. A transaction that can't cover the base fee at all scores
+/// lowest.
+fn effective_gas_price(transaction: &PendingTransaction, base_fee: Option<U256>) -> U256 {
+    effective_gas_price_from_fees(
+        transaction.gas_price(),
+        transaction.max_priority_fee_per_gas(),
+        base_fee,
+    )
+}
+
+/// Pure core of [`effective_gas_price`], operating on the raw fee fields instead of a
+/// [`PendingTransaction`], so the scoring math can be exercised without constructing one.
+fn effective_gas_price_from_fees(
+    max_fee_per_gas: U256,
+    max_priority_fee_per_gas: Option<U256>,
+    base_fee: Option<U256>,
+) -> U256 {
+    match base_fee {
+        Some(base_fee) if max_fee_per_gas >= base_fee => match max_priority_fee_per_gas {
+            Some(max_priority_fee_per_gas) => {
+                max_fee_per_gas.min(base_fee + max_priority_fee_per_gas)
+            }
+            None => max_fee_per_gas,
+        },
+        Some(_) => U256::ZERO,
+        None => max_fee_per_gas,
+    }
+}
+
+/// Returns `value` increased by `min_bump_percentage` percent, rounded down, the minimum a
+/// replacement transaction's fee must reach per EIP-1559's replace-by-fee rule.
+fn bumped(value: U256, min_bump_percentage: u64) -> U256 {
+    value + value * U256::from(min_bump_percentage) / U256::from(100)
+}
+
+/// Returns whether `replacement`'s fees exceed `existing`'s by at least `min_bump_percentage`,
+/// per EIP-1559's replace-by-fee rule: both the max fee per gas and, for 1559 transactions, the
+/// max priority fee per gas must clear the bump.
+fn is_sufficient_fee_bump(
+    existing: &PendingTransaction,
+    replacement: &PendingTransaction,
+    min_bump_percentage: u64,
+) -> bool {
+    if replacement.gas_price() < bumped(existing.gas_price(), min_bump_percentage) {
+        return false;
+    }
+
+    match (
+        existing.max_priority_fee_per_gas(),
+        replacement.max_priority_fee_per_gas(),
+    ) {
+        (Some(existing_tip), Some(replacement_tip)) => {
+            replacement_tip >= bumped(existing_tip, min_bump_percentage)
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bumped_rounds_down_the_percentage_increase() {
+        assert_eq!(bumped(U256::from(100), 10), U256::from(110));
+        // 10% of 105 is 10.5, which rounds down.
+        assert_eq!(bumped(U256::from(105), 10), U256::from(115));
+    }
+
+    #[test]
+    fn bumped_by_zero_percent_is_unchanged() {
+        assert_eq!(bumped(U256::from(100), 0), U256::from(100));
+    }
+
+    #[test]
+    fn bumped_of_zero_is_zero() {
+        assert_eq!(bumped(U256::ZERO, 10), U256::ZERO);
+    }
+
+    #[test]
+    fn effective_gas_price_without_base_fee_is_the_flat_gas_price() {
+        assert_eq!(
+            effective_gas_price_from_fees(U256::from(100), None, None),
+            U256::from(100)
+        );
+    }
+
+    #[test]
+    fn effective_gas_price_below_base_fee_scores_zero() {
+        assert_eq!(
+            effective_gas_price_from_fees(U256::from(10), Some(U256::from(5)), Some(U256::from(20))),
+            U256::ZERO
+        );
+    }
+
+    #[test]
+    fn effective_gas_price_is_capped_at_max_fee() {
+        // base_fee 80 plus a tip of 30 would be 110, but max_fee caps the price at 100.
+        assert_eq!(
+            effective_gas_price_from_fees(U256::from(100), Some(U256::from(30)), Some(U256::from(80))),
+            U256::from(100)
+        );
+    }
+
+    #[test]
+    fn effective_gas_price_legacy_transaction_pays_its_flat_gas_price() {
+        // No priority fee set: a legacy transaction pays its whole gas price, whatever the base fee.
+        assert_eq!(
+            effective_gas_price_from_fees(U256::from(100), None, Some(U256::from(80))),
+            U256::from(100)
+        );
+    }
 }
@@ -1,9 +1,10 @@
-use std::{collections::VecDeque, fmt::Debug, sync::Arc};
+use std::{fmt::Debug, sync::Arc, time::{SystemTime, UNIX_EPOCH}};
 
 use rethnet_eth::{
     block::{Block, Header},
+    log::Log,
     receipt::TypedReceipt,
-    Address, B64, U256, U64,
+    Address, Bloom, B64, U256, U64,
 };
 use revm::primitives::{CfgEnv, ExecutionResult, SpecId};
 use tokio::sync::RwLock;
@@ -13,9 +14,22 @@ use crate::{
     blockchain::SyncBlockchain,
     state::SyncState,
     trace::{Trace, TraceCollector},
-    BlockBuilder, BlockOptions, BlockTransactionError, MemPool, RandomHashGenerator,
+    BlockBuilder, BlockOptions, BlockTransactionError, MemPool, PendingTransaction,
+    RandomHashGenerator,
 };
 
+/// Overrides for the block environment used when simulating transactions, as opposed to mining
+/// them for real.
+#[derive(Clone, Debug, Default)]
+pub struct BlockOverrides {
+    /// Overridden block timestamp
+    pub timestamp: Option<U256>,
+    /// Overridden block base fee
+    pub base_fee: Option<U256>,
+    /// Overridden block number
+    pub number: Option<U256>,
+}
+
 /// The result of mining a block.
 pub struct MineBlockResult {
     /// Mined block
@@ -28,6 +42,82 @@ pub struct MineBlockResult {
     transaction_traces: Vec<Trace>,
 }
 
+impl MineBlockResult {
+    /// Returns the block's logs, flattened across all of its transactions' receipts and
+    /// assigned the `log_index`/`transaction_log_index` a consumer would need to serve
+    /// `eth_getLogs`/subscription queries, alongside the logs bloom for the whole block. This
+    /// spares callers from having to re-derive per-block indices themselves.
+    pub fn logs(&self) -> BlockLogs {
+        let mut logs = Vec::new();
+        let mut bloom = Bloom::default();
+        let mut log_index = 0usize;
+
+        for (transaction_index, receipt) in self.transaction_receipts.iter().enumerate() {
+            for (transaction_log_index, log) in receipt.transaction_logs().iter().enumerate() {
+                bloom |= receipt.logs_bloom();
+
+                logs.push(BlockLog {
+                    inner: log.clone(),
+                    transaction_index,
+                    log_index,
+                    transaction_log_index,
+                });
+
+                log_index += 1;
+            }
+        }
+
+        BlockLogs { logs, bloom }
+    }
+}
+
+/// A single log as it appears within a mined block: the underlying log together with the
+/// indices needed to place it among the block's other logs.
+#[derive(Clone, Debug)]
+pub struct BlockLog {
+    /// The underlying log
+    pub inner: Log,
+    /// The index of the transaction that emitted this log within the block
+    pub transaction_index: usize,
+    /// The log's index within the block, across all of the block's transactions
+    pub log_index: usize,
+    /// The log's index within the logs emitted by its own transaction
+    pub transaction_log_index: usize,
+}
+
+/// The flattened, block-global view of the logs emitted by a mined block's transactions,
+/// alongside the block's aggregated logs bloom.
+#[derive(Clone, Debug)]
+pub struct BlockLogs {
+    logs: Vec<BlockLog>,
+    bloom: Bloom,
+}
+
+impl BlockLogs {
+    /// The block's logs, in the order they were emitted, with their block-global indices.
+    pub fn logs(&self) -> &[BlockLog] {
+        &self.logs
+    }
+
+    /// The logs bloom obtained by aggregating every transaction receipt's own logs bloom.
+    pub fn bloom(&self) -> &Bloom {
+        &self.bloom
+    }
+}
+
+/// Extension trait distinguishing the logs an individual transaction produced from the
+/// block-cumulative set that [`MineBlockResult::logs`] assembles.
+pub trait ReceiptLogs {
+    /// The logs produced by this transaction alone.
+    fn transaction_logs(&self) -> &[Log];
+}
+
+impl ReceiptLogs for TypedReceipt {
+    fn transaction_logs(&self) -> &[Log] {
+        self.logs()
+    }
+}
+
 /// An error that occurred while mining a block.
 #[derive(Debug, thiserror::Error)]
 pub enum MineBlockError<BE, SE> {
@@ -51,6 +141,50 @@ pub enum MineBlockError<BE, SE> {
     TransactionPoolUpdate(SE),
 }
 
+/// A block built speculatively from the mempool's ready transactions, executed atop the chain's
+/// current state but never mined: nothing is inserted into the blockchain, no transaction is
+/// removed from the pool, and the chain's own state is left untouched, since this runs against a
+/// throwaway copy of it. This is the `"pending"` block: consumers can resolve balances, nonces
+/// and `block_hash` against [`Self::state`] the same way they would for a mined block, matching
+/// node semantics where `BlockId::Pending` resolves to the miner's speculative block.
+pub struct PendingBlock<SE> {
+    header: Header,
+    receipts: Vec<TypedReceipt>,
+    state: Box<dyn SyncState<SE>>,
+}
+
+impl<SE: Debug + Send + 'static> PendingBlock<SE> {
+    /// The header the block would have, were it mined right now, with `gas_used`,
+    /// `receipts_root` and `state_root` already computed.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// The receipts the block's transactions would produce.
+    pub fn receipts(&self) -> &[TypedReceipt] {
+        &self.receipts
+    }
+
+    /// A read-only handle onto the world state that would result from mining this block.
+    pub fn state(&self) -> &dyn SyncState<SE> {
+        self.state.as_ref()
+    }
+}
+
+/// An error that occurred while estimating the gas requirement of a transaction.
+#[derive(Debug, thiserror::Error)]
+pub enum EstimateGasError<BE, SE> {
+    /// The transaction doesn't succeed even at the block gas limit.
+    #[error("Transaction failed at the block gas limit: {failure:?}")]
+    TransactionFailed {
+        /// The execution result of the failing transaction
+        failure: ExecutionResult,
+    },
+    /// An error that occurred while simulating the transaction.
+    #[error(transparent)]
+    Simulation(#[from] MineBlockError<BE, SE>),
+}
+
 /// Type for mining blocks.
 pub struct BlockMiner<BE, SE>
 where
@@ -117,13 +251,24 @@ where
         };
 
         let mut transaction_pool = self.transaction_pool.write().await;
-        let mut pending_transactions: VecDeque<_> =
-            transaction_pool.pending_transactions().cloned().collect();
+
+        // Order by the same base fee the block is built with.
+        let base_fee = if self.cfg.spec_id >= SpecId::LONDON {
+            Some(match base_fee {
+                Some(base_fee) => base_fee,
+                None => calculate_next_base_fee(&self.blockchain.read().await.last_block().header),
+            })
+        } else {
+            None
+        };
+
+        let mut transaction_queue = transaction_pool.pending_transactions_by_priority(base_fee);
 
         let mut results = Vec::new();
         let mut traces = Vec::new();
 
-        while let Some(transaction) = pending_transactions.pop_front() {
+        while let Some(transaction) = transaction_queue.next() {
+            let sender = *transaction.caller();
             let mut tracer = TraceCollector::default();
 
             let transaction_hash = transaction.hash().clone();
@@ -147,6 +292,10 @@ where
                     traces.push(tracer.into_trace());
 
                     transaction_pool.remove_transaction(&transaction_hash);
+
+                    // Pull the sender's next nonce-ordered transaction into contention now that
+                    // its predecessor has been included.
+                    transaction_queue.mark_included(sender);
                 }
             }
         }
@@ -178,6 +327,257 @@ where
             transaction_traces: traces,
         })
     }
+
+    /// Builds the `"pending"` block: the block that would result from mining right now, executed
+    /// against a throwaway copy of the chain's state so that the chain's actual state, the
+    /// blockchain, and the mempool are all left untouched. Transactions are taken from the
+    /// mempool's ready queue in the same priority order [`Self::mine_block`] would use.
+    pub async fn pending_block(&self) -> Result<PendingBlock<SE>, MineBlockError<BE, SE>> {
+        let pending_state: Arc<RwLock<Box<dyn SyncState<SE>>>> =
+            Arc::new(RwLock::new(self.state.read().await.clone()));
+
+        let (parent_block, base_fee) = {
+            let blockchain = self.blockchain.read().await;
+            let parent_block = blockchain.last_block();
+            let base_fee = if self.cfg.spec_id >= SpecId::LONDON {
+                Some(calculate_next_base_fee(&parent_block.header))
+            } else {
+                None
+            };
+
+            (parent_block, base_fee)
+        };
+
+        let mut block_builder = BlockBuilder::new(
+            self.blockchain.clone(),
+            pending_state.clone(),
+            self.cfg.clone(),
+            parent_block.header.clone(),
+            BlockOptions {
+                beneficiary: Some(self.beneficiary),
+                number: Some(parent_block.header.number + U256::from(1)),
+                gas_limit: Some(self.block_gas_limit),
+                timestamp: Some(U256::from(
+                    SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .expect("Current time must be after unix epoch")
+                        .as_secs(),
+                )),
+                mix_hash: if self.cfg.spec_id >= SpecId::MERGE {
+                    Some(self.prevrandao_generator.next_value())
+                } else {
+                    None
+                },
+                nonce: Some(if self.cfg.spec_id >= SpecId::MERGE {
+                    B64::ZERO
+                } else {
+                    B64::from(U64::from(42))
+                }),
+                base_fee,
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        let transaction_pool = self.transaction_pool.read().await;
+        let mut transaction_queue = transaction_pool.pending_transactions_by_priority(base_fee);
+
+        while let Some(transaction) = transaction_queue.next() {
+            let sender = *transaction.caller();
+
+            match block_builder.add_transaction(transaction, None).await {
+                Err(BlockTransactionError::ExceedsBlockGasLimit) => continue,
+                Err(e) => {
+                    block_builder
+                        .abort()
+                        .await
+                        .map_err(MineBlockError::BlockAbort)?;
+
+                    return Err(MineBlockError::BlockTransaction(e));
+                }
+                Ok(_) => transaction_queue.mark_included(sender),
+            }
+        }
+
+        let BlockResult {
+            block, receipts, ..
+        } = block_builder
+            .finalize(vec![(self.beneficiary, U256::ZERO)], None)
+            .await
+            .map_err(MineBlockError::BlockFinalize)?;
+
+        let state = pending_state.read().await.clone();
+
+        Ok(PendingBlock {
+            header: block.header,
+            receipts,
+            state,
+        })
+    }
+
+    /// Executes an ordered batch of transactions against the current pending state, without
+    /// inserting a block or mutating any committed state. Later transactions in the batch observe
+    /// the state changes made by earlier ones, but the entire batch is discarded once it's done,
+    /// making this suitable for `eth_call`-style speculative and bundled execution.
+    pub async fn simulate_transactions(
+        &self,
+        transactions: Vec<PendingTransaction>,
+        block_overrides: BlockOverrides,
+    ) -> Result<Vec<(ExecutionResult, Trace)>, MineBlockError<BE, SE>> {
+        let mut block_builder = {
+            let blockchain = self.blockchain.read().await;
+            let parent_block = blockchain.last_block();
+
+            BlockBuilder::new(
+                self.blockchain.clone(),
+                self.state.clone(),
+                self.cfg.clone(),
+                parent_block.header.clone(),
+                BlockOptions {
+                    beneficiary: Some(self.beneficiary),
+                    number: Some(
+                        block_overrides
+                            .number
+                            .unwrap_or_else(|| parent_block.header.number.clone()),
+                    ),
+                    gas_limit: Some(self.block_gas_limit),
+                    timestamp: Some(
+                        block_overrides
+                            .timestamp
+                            .unwrap_or_else(|| parent_block.header.timestamp),
+                    ),
+                    mix_hash: if self.cfg.spec_id >= SpecId::MERGE {
+                        Some(self.prevrandao_generator.next_value())
+                    } else {
+                        None
+                    },
+                    nonce: Some(if self.cfg.spec_id >= SpecId::MERGE {
+                        B64::ZERO
+                    } else {
+                        B64::from(U64::from(42))
+                    }),
+                    base_fee: if self.cfg.spec_id >= SpecId::LONDON {
+                        Some(block_overrides.base_fee.unwrap_or_else(|| {
+                            calculate_next_base_fee(&parent_block.header)
+                        }))
+                    } else {
+                        None
+                    },
+                    ..Default::default()
+                },
+            )
+            .await?
+        };
+
+        let mut results = Vec::with_capacity(transactions.len());
+
+        for transaction in transactions {
+            let mut tracer = TraceCollector::default();
+
+            match block_builder
+                .add_transaction(transaction, Some(&mut tracer))
+                .await
+            {
+                Err(e) => {
+                    block_builder
+                        .abort()
+                        .await
+                        .map_err(MineBlockError::BlockAbort)?;
+
+                    return Err(MineBlockError::BlockTransaction(e));
+                }
+                Ok(result) => results.push((result, tracer.into_trace())),
+            }
+        }
+
+        // Discard every state change made by the batch; nothing gets persisted or mined.
+        block_builder
+            .abort()
+            .await
+            .map_err(MineBlockError::BlockAbort)?;
+
+        Ok(results)
+    }
+
+    /// Estimates the minimal gas limit under which the given transaction succeeds, by binary
+    /// searching between the intrinsic gas floor and the block gas limit. Each trial is run
+    /// against a fresh simulation so that state mutations from one probe never leak into the
+    /// next. Returns the limit found plus a safety margin of 1,000 gas, capped at the block gas
+    /// limit.
+    pub async fn estimate_gas(
+        &self,
+        transaction: PendingTransaction,
+        block_overrides: BlockOverrides,
+    ) -> Result<U256, EstimateGasError<BE, SE>> {
+        const MIN_GAS_LIMIT: u64 = 21_000;
+        const GAS_LIMIT_SAFETY_MARGIN: u64 = 1_000;
+
+        let block_gas_limit = self.block_gas_limit;
+
+        // First, confirm the transaction succeeds at all when given the full block gas limit.
+        let (result, _trace) = self
+            .simulate_one(
+                transaction.with_gas_limit(block_gas_limit),
+                block_overrides.clone(),
+            )
+            .await?;
+
+        if !matches!(result, ExecutionResult::Success { .. }) {
+            return Err(EstimateGasError::TransactionFailed { failure: result });
+        }
+
+        let mut lower_bound = U256::from(MIN_GAS_LIMIT);
+        let mut upper_bound = block_gas_limit;
+
+        while lower_bound < upper_bound {
+            let mid = binary_search_midpoint(lower_bound, upper_bound);
+
+            let (result, _trace) = self
+                .simulate_one(transaction.with_gas_limit(mid), block_overrides.clone())
+                .await?;
+
+            let succeeded = matches!(result, ExecutionResult::Success { .. });
+            (lower_bound, upper_bound) = narrow_gas_search(lower_bound, upper_bound, mid, succeeded);
+        }
+
+        Ok((upper_bound + U256::from(GAS_LIMIT_SAFETY_MARGIN)).min(block_gas_limit))
+    }
+
+    /// Runs a single transaction through [`Self::simulate_transactions`] and returns its lone
+    /// result and trace.
+    async fn simulate_one(
+        &self,
+        transaction: PendingTransaction,
+        block_overrides: BlockOverrides,
+    ) -> Result<(ExecutionResult, Trace), EstimateGasError<BE, SE>> {
+        let mut results = self
+            .simulate_transactions(vec![transaction], block_overrides)
+            .await?;
+
+        Ok(results.remove(0))
+    }
+}
+
+/// Computes the midpoint [`BlockMiner::estimate_gas`] probes next in its binary search over
+/// `[lower_bound, upper_bound]`.
+fn binary_search_midpoint(lower_bound: U256, upper_bound: U256) -> U256 {
+    lower_bound + (upper_bound - lower_bound) / U256::from(2)
+}
+
+/// Narrows [`BlockMiner::estimate_gas`]'s binary search bounds given whether the probe at `mid`
+/// succeeded: a successful probe tightens the upper bound down to `mid`, since it's now known to
+/// be enough; a failing one raises the lower bound past it, since `mid` is now known not to be.
+fn narrow_gas_search(
+    lower_bound: U256,
+    upper_bound: U256,
+    mid: U256,
+    succeeded: bool,
+) -> (U256, U256) {
+    if succeeded {
+        (lower_bound, mid)
+    } else {
+        (mid + U256::from(1), upper_bound)
+    }
 }
 
 /// Calculates the next base fee for a post-London block, given the parent's header.
@@ -215,3 +615,79 @@ fn calculate_next_base_fee(parent: &Header) -> U256 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs the same bisection [`BlockMiner::estimate_gas`] does, against a plain
+    /// `succeeds(gas_limit)` predicate instead of an EVM simulation, so the search itself can be
+    /// checked without standing up a transaction/state/blockchain.
+    fn binary_search_gas_limit(
+        mut lower_bound: U256,
+        mut upper_bound: U256,
+        mut succeeds: impl FnMut(U256) -> bool,
+    ) -> U256 {
+        while lower_bound < upper_bound {
+            let mid = binary_search_midpoint(lower_bound, upper_bound);
+            let succeeded = succeeds(mid);
+            (lower_bound, upper_bound) = narrow_gas_search(lower_bound, upper_bound, mid, succeeded);
+        }
+
+        upper_bound
+    }
+
+    #[test]
+    fn binary_search_finds_the_exact_threshold() {
+        let threshold = U256::from(30_000);
+
+        let result = binary_search_gas_limit(U256::from(21_000), U256::from(1_000_000), |mid| {
+            mid >= threshold
+        });
+
+        assert_eq!(result, threshold);
+    }
+
+    #[test]
+    fn binary_search_handles_a_threshold_at_the_lower_bound() {
+        let result = binary_search_gas_limit(U256::from(21_000), U256::from(1_000_000), |_mid| {
+            true
+        });
+
+        assert_eq!(result, U256::from(21_000));
+    }
+
+    #[test]
+    fn binary_search_handles_a_threshold_at_the_upper_bound() {
+        let upper_bound = U256::from(1_000_000);
+
+        let result =
+            binary_search_gas_limit(U256::from(21_000), upper_bound, |mid| mid >= upper_bound);
+
+        assert_eq!(result, upper_bound);
+    }
+
+    #[test]
+    fn binary_search_midpoint_rounds_down() {
+        assert_eq!(
+            binary_search_midpoint(U256::from(0), U256::from(3)),
+            U256::from(1)
+        );
+    }
+
+    #[test]
+    fn narrow_gas_search_on_success_lowers_the_upper_bound() {
+        let (lower_bound, upper_bound) =
+            narrow_gas_search(U256::from(0), U256::from(10), U256::from(4), true);
+
+        assert_eq!((lower_bound, upper_bound), (U256::from(0), U256::from(4)));
+    }
+
+    #[test]
+    fn narrow_gas_search_on_failure_raises_the_lower_bound_past_mid() {
+        let (lower_bound, upper_bound) =
+            narrow_gas_search(U256::from(0), U256::from(10), U256::from(4), false);
+
+        assert_eq!((lower_bound, upper_bound), (U256::from(5), U256::from(10)));
+    }
+}
@@ -0,0 +1,268 @@
+use rethnet_eth::{Address, Bytes, U256};
+use revm::{
+    interpreter::{CallInputs, CreateInputs, Gas, InstructionResult, Interpreter},
+    primitives::ExecutionResult,
+    EVMData, Inspector,
+};
+
+/// The kind of call that entered a trace frame, mirroring the distinctions the EVM itself makes
+/// between `CALL`/`CALLCODE`/`DELEGATECALL`/`STATICCALL` and contract creation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallKind {
+    /// A `CALL`
+    Call,
+    /// A `CALLCODE`
+    CallCode,
+    /// A `DELEGATECALL`
+    DelegateCall,
+    /// A `STATICCALL`
+    StaticCall,
+    /// A `CREATE`
+    Create,
+    /// A `CREATE2`
+    Create2,
+}
+
+/// The message emitted when a new call/create frame is entered.
+#[derive(Clone, Debug)]
+pub struct BeforeMessage {
+    /// The kind of call that entered this frame
+    pub kind: CallKind,
+    /// The nested call depth, where the top-level frame is `0`
+    pub depth: usize,
+    /// The caller of this frame
+    pub caller: Address,
+    /// The address whose storage/balance this frame affects. For `CALLCODE`/`DELEGATECALL` this
+    /// is the caller's own address, since code executes in the caller's context.
+    pub address: Option<Address>,
+    /// The address of the code being executed. Differs from `address` for
+    /// `CALLCODE`/`DELEGATECALL`, which execute another contract's code in the caller's context.
+    pub code_address: Option<Address>,
+    /// The value transferred with this call. `DELEGATECALL`/`STATICCALL` never transfer value.
+    pub value: U256,
+    /// The calldata (for calls) or initcode (for creates) passed to this frame
+    pub data: Bytes,
+    /// The gas supplied to this frame
+    pub gas_limit: u64,
+}
+
+/// A single message collected while tracing a transaction's execution.
+#[derive(Clone, Debug)]
+pub enum TraceMessage {
+    /// A new call/create frame was entered.
+    Before(Box<BeforeMessage>),
+    /// A single opcode step was executed.
+    Step(TracingStep),
+    /// A call/create frame returned.
+    After(ExecutionResult),
+}
+
+/// A single interpreter step, kept intentionally small since steps vastly outnumber frames.
+#[derive(Clone, Debug)]
+pub struct TracingStep {
+    /// The program counter at the time of the step
+    pub pc: usize,
+}
+
+/// The result of tracing a transaction: a flat, depth-annotated sequence of messages that a
+/// consumer can walk to reconstruct the call tree.
+#[derive(Clone, Debug, Default)]
+pub struct Trace {
+    /// The messages collected while tracing
+    pub messages: Vec<TraceMessage>,
+}
+
+/// An EVM inspector that records a [`Trace`] of call frames, their kind, addresses, value,
+/// input, gas, and nested depth, as well as each step taken.
+#[derive(Clone, Debug, Default)]
+pub struct TraceCollector {
+    trace: Trace,
+    depth: usize,
+}
+
+impl TraceCollector {
+    /// Consumes the collector, returning the collected [`Trace`].
+    pub fn into_trace(self) -> Trace {
+        self.trace
+    }
+}
+
+impl<DB> Inspector<DB> for TraceCollector
+where
+    DB: revm::Database,
+{
+    fn step(&mut self, interp: &mut Interpreter<'_>, _data: &mut EVMData<'_, DB>) -> InstructionResult {
+        self.trace.messages.push(TraceMessage::Step(TracingStep {
+            pc: interp.program_counter(),
+        }));
+
+        InstructionResult::Continue
+    }
+
+    fn call(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        inputs: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        let kind = match inputs.context.scheme {
+            revm::primitives::CallScheme::Call => CallKind::Call,
+            revm::primitives::CallScheme::CallCode => CallKind::CallCode,
+            revm::primitives::CallScheme::DelegateCall => CallKind::DelegateCall,
+            revm::primitives::CallScheme::StaticCall => CallKind::StaticCall,
+        };
+
+        // `context.address` is already the currently-executing contract's own address for every
+        // call scheme: the callee for `CALL`/`STATICCALL`, and the calling contract itself for
+        // `CALLCODE`/`DELEGATECALL` (which execute the target's code in that context). It's never
+        // `context.caller`, which for `DELEGATECALL` is the *outer* caller whose `msg.sender` is
+        // being preserved through the call, not the frame's own address.
+        self.trace
+            .messages
+            .push(TraceMessage::Before(Box::new(BeforeMessage {
+                kind,
+                depth: self.depth,
+                caller: inputs.context.caller,
+                address: Some(inputs.context.address),
+                code_address: Some(inputs.context.code_address),
+                value: inputs.transfer.value,
+                data: inputs.input.clone(),
+                gas_limit: inputs.gas_limit,
+            })));
+
+        self.depth += 1;
+
+        (InstructionResult::Continue, Gas::new(0), Bytes::new())
+    }
+
+    fn call_end(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        _inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.depth -= 1;
+        self.trace
+            .messages
+            .push(TraceMessage::After(instruction_result_to_execution_result(
+                ret,
+                remaining_gas,
+                out,
+            )));
+
+        (ret, remaining_gas, out)
+    }
+
+    fn create(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        inputs: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        let kind = match inputs.scheme {
+            revm::primitives::CreateScheme::Create => CallKind::Create,
+            revm::primitives::CreateScheme::Create2 { .. } => CallKind::Create2,
+        };
+
+        self.trace
+            .messages
+            .push(TraceMessage::Before(Box::new(BeforeMessage {
+                kind,
+                depth: self.depth,
+                caller: inputs.caller,
+                // The created contract's address isn't known until after the frame runs.
+                address: None,
+                code_address: None,
+                value: inputs.value,
+                data: inputs.init_code.clone(),
+                gas_limit: inputs.gas_limit,
+            })));
+
+        self.depth += 1;
+
+        (InstructionResult::Continue, None, Gas::new(0), Bytes::new())
+    }
+
+    fn create_end(
+        &mut self,
+        _data: &mut EVMData<'_, DB>,
+        _inputs: &CreateInputs,
+        ret: InstructionResult,
+        address: Option<Address>,
+        remaining_gas: Gas,
+        out: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.depth -= 1;
+        self.trace
+            .messages
+            .push(TraceMessage::After(instruction_result_to_execution_result(
+                ret,
+                remaining_gas,
+                out,
+            )));
+
+        (ret, address, remaining_gas, out)
+    }
+}
+
+/// Converts the raw outcome of a call/create frame into an [`ExecutionResult`], so that
+/// per-frame outcomes can share a representation with the top-level transaction result.
+fn instruction_result_to_execution_result(
+    ret: InstructionResult,
+    gas: Gas,
+    output: Bytes,
+) -> ExecutionResult {
+    use revm::primitives::{Eval, Halt};
+
+    if ret.is_ok() {
+        ExecutionResult::Success {
+            reason: Eval::Return,
+            gas_used: gas.spend(),
+            gas_refunded: gas.refunded() as u64,
+            logs: Vec::new(),
+            output: revm::primitives::Output::Call(output),
+        }
+    } else if ret.is_revert() {
+        ExecutionResult::Revert {
+            gas_used: gas.spend(),
+            output,
+        }
+    } else {
+        ExecutionResult::Halt {
+            reason: instruction_result_to_halt(ret),
+            gas_used: gas.spend(),
+        }
+    }
+}
+
+/// Maps a halting [`InstructionResult`] to the [`Halt`] reason it represents, so that a trace
+/// frame's `Halt` matches the EVM's actual failure instead of being mislabeled.
+fn instruction_result_to_halt(ret: InstructionResult) -> revm::primitives::Halt {
+    use revm::primitives::Halt;
+
+    match ret {
+        InstructionResult::OutOfGas => Halt::OutOfGas,
+        InstructionResult::OpcodeNotFound => Halt::OpcodeNotFound,
+        InstructionResult::InvalidFEOpcode => Halt::InvalidFEOpcode,
+        InstructionResult::InvalidJump => Halt::InvalidJump,
+        InstructionResult::NotActivated => Halt::NotActivated,
+        InstructionResult::StackUnderflow => Halt::StackUnderflow,
+        InstructionResult::StackOverflow => Halt::StackOverflow,
+        InstructionResult::OutOfOffset => Halt::OutOfOffset,
+        InstructionResult::CreateCollision => Halt::CreateCollision,
+        InstructionResult::OverflowPayment => Halt::OverflowPayment,
+        InstructionResult::PrecompileError => Halt::PrecompileError,
+        InstructionResult::NonceOverflow => Halt::NonceOverflow,
+        InstructionResult::CreateContractSizeLimit => Halt::CreateContractSizeLimit,
+        InstructionResult::CreateContractStartingWithEF => Halt::CreateContractStartingWithEF,
+        InstructionResult::CreateInitCodeSizeLimit => Halt::CreateInitCodeSizeLimit,
+        InstructionResult::StateChangeDuringStaticCall => Halt::StateChangeDuringStaticCall,
+        InstructionResult::CallNotAllowedInsideStatic => Halt::CallNotAllowedInsideStatic,
+        InstructionResult::OutOfFund => Halt::OutOfFund,
+        InstructionResult::CallTooDeep => Halt::CallTooDeep,
+        // `Continue`/`Return`/`Stop`/`SelfDestruct`/`Revert` are handled by `is_ok`/`is_revert`
+        // above and never reach this branch; this only remains as a last resort for a future
+        // `InstructionResult` variant this match hasn't been updated to cover yet.
+        _ => Halt::OutOfGas,
+    }
+}
@@ -6,6 +6,16 @@ use rethnet_eth::B256;
 
 use crate::{state::StateManager, transaction::PendingTransaction};
 
+/// The outcome of [`MemPool::update`]: the transactions that were promoted from the future queue
+/// to pending, and the ones that were discarded outright.
+#[napi(object)]
+pub struct MemPoolUpdate {
+    /// Transactions moved from the future queue to the pending queue
+    pub promoted: Vec<PendingTransaction>,
+    /// Transactions removed from the pool entirely
+    pub discarded: Vec<PendingTransaction>,
+}
+
 /// The mempool contains transactions pending inclusion in the blockchain.
 #[napi]
 pub struct MemPool {
@@ -14,7 +24,7 @@ pub struct MemPool {
 
 impl Default for MemPool {
     fn default() -> Self {
-        Self::new()
+        Self::new(None, None, None, None, None)
     }
 }
 
@@ -28,10 +38,31 @@ impl Deref for MemPool {
 
 #[napi]
 impl MemPool {
-    #[doc = "Constructs a new [`MemPool`]."]
+    #[doc = "Constructs a new [`MemPool`], optionally overriding the minimum percentage by which a replacement transaction must bump fees over the transaction it replaces (defaults to 10), the maximum number of transactions the pool may hold, the maximum number of transactions a single sender may have in the pool, the maximum number of blocks a future transaction may sit in the pool before it's considered stale, and the maximum number of future transactions a single sender may have in the pool."]
     #[napi(constructor)]
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(
+        min_bump_percentage: Option<u32>,
+        max_count: Option<u32>,
+        max_count_per_sender: Option<u32>,
+        future_transaction_ttl: Option<u32>,
+        max_future_transactions_per_sender: Option<u32>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(
+                rethnet_evm::MemPool::with_limits(
+                    u64::from(min_bump_percentage.unwrap_or(10)),
+                    max_count.map(|max_count| max_count as usize),
+                    max_count_per_sender.map(|max_count_per_sender| max_count_per_sender as usize),
+                )
+                .with_future_transaction_limits(
+                    future_transaction_ttl.map(u64::from),
+                    max_future_transactions_per_sender
+                        .map(|max_future_transactions_per_sender| {
+                            max_future_transactions_per_sender as usize
+                        }),
+                ),
+            )),
+        }
     }
 
     #[doc = "Creates a deep clone of the [`MemPool`]"]
@@ -67,15 +98,29 @@ impl MemPool {
         self.write().await.remove_transaction(&hash).is_some()
     }
 
-    #[doc = "Updates the instance, moving any future transactions to the pending status, if their nonces are high enough."]
+    #[doc = "Updates the instance, moving any future transactions to the pending status if their nonces are high enough, and evicting any that have been superseded or have gone stale. Returns the transactions that were promoted and discarded, respectively."]
     #[napi]
-    pub async fn update(&self, state_manager: &StateManager) -> napi::Result<()> {
+    pub async fn update(&self, state_manager: &StateManager) -> napi::Result<MemPoolUpdate> {
         let state = state_manager.read().await;
 
-        self.write()
+        let update = self
+            .write()
             .await
             .update(&*state)
-            .map_err(|e| napi::Error::new(Status::GenericFailure, e.to_string()))
+            .map_err(|e| napi::Error::new(Status::GenericFailure, e.to_string()))?;
+
+        Ok(MemPoolUpdate {
+            promoted: update
+                .promoted
+                .into_iter()
+                .map(PendingTransaction::from)
+                .collect(),
+            discarded: update
+                .discarded
+                .into_iter()
+                .map(PendingTransaction::from)
+                .collect(),
+        })
     }
 
     #[doc = "Returns all transactions in the mem pool."]
@@ -86,8 +131,8 @@ impl MemPool {
         mempool
             .pending_transactions()
             .iter()
-            .chain(mempool.future_transactions().iter())
             .cloned()
+            .chain(mempool.future_transactions().cloned())
             .map(PendingTransaction::from)
             .collect()
     }
@@ -98,7 +143,6 @@ impl MemPool {
         self.read()
             .await
             .future_transactions()
-            .iter()
             .cloned()
             .map(PendingTransaction::from)
             .collect()
@@ -119,7 +163,7 @@ impl MemPool {
     #[doc = "Returns whether the [`MemPool`] contains any future transactions."]
     #[napi]
     pub async fn has_future_transactions(&self) -> bool {
-        !self.read().await.future_transactions().is_empty()
+        self.read().await.future_transactions().next().is_some()
     }
 
     #[doc = "Returns whether the [`MemPool`] contains any pending transactions."]
@@ -18,9 +18,32 @@ use crate::{
     context::{Context, RethnetContext},
     mempool::MemPool,
     state::StateManager,
+    transaction::PendingTransaction,
 };
 
-use self::result::MineBlockResult;
+use self::result::{MineBlockResult, PendingBlockResult, TransactionSimulationResult};
+
+/// Overrides for the block environment used by [`BlockMiner::simulate_transactions`].
+#[napi(object)]
+#[derive(Default)]
+pub struct BlockOverrides {
+    /// Overridden block timestamp
+    pub timestamp: Option<BigInt>,
+    /// Overridden block base fee
+    pub base_fee: Option<BigInt>,
+    /// Overridden block number
+    pub number: Option<BigInt>,
+}
+
+impl TryCast<rethnet_evm::BlockOverrides> for BlockOverrides {
+    fn try_cast(self) -> napi::Result<rethnet_evm::BlockOverrides> {
+        Ok(rethnet_evm::BlockOverrides {
+            timestamp: self.timestamp.map(BigInt::try_cast).transpose()?,
+            base_fee: self.base_fee.map(BigInt::try_cast).transpose()?,
+            number: self.number.map(BigInt::try_cast).transpose()?,
+        })
+    }
+}
 
 #[napi]
 pub struct BlockMiner {
@@ -101,4 +124,83 @@ impl BlockMiner {
                 |result| Ok(MineBlockResult::from(result)),
             )
     }
+
+    #[doc = "Executes a batch of transactions against the current pending state, without mining a block or persisting any state changes. Later transactions in the batch observe the state changes made by earlier ones."]
+    #[napi]
+    pub async fn simulate_transactions(
+        &self,
+        transactions: Vec<&PendingTransaction>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> napi::Result<Vec<TransactionSimulationResult>> {
+        let transactions = transactions
+            .into_iter()
+            .map(|transaction| (**transaction).clone())
+            .collect();
+        let block_overrides = block_overrides.unwrap_or_default().try_cast()?;
+
+        let miner = self.miner.clone();
+
+        let results = self
+            .context
+            .runtime()
+            .spawn(async move {
+                let miner = miner.read().await;
+                miner
+                    .simulate_transactions(transactions, block_overrides)
+                    .await
+            })
+            .await
+            .unwrap()
+            .map_err(|e| napi::Error::new(Status::GenericFailure, e.to_string()))?;
+
+        Ok(results
+            .into_iter()
+            .map(|(result, trace)| TransactionSimulationResult::new(result, trace))
+            .collect())
+    }
+
+    #[doc = "Estimates the minimal gas limit under which the given transaction succeeds, and returns it plus a safety margin of 1,000 gas, capped at the block gas limit."]
+    #[napi]
+    pub async fn estimate_gas(
+        &self,
+        transaction: &PendingTransaction,
+        block_overrides: Option<BlockOverrides>,
+    ) -> napi::Result<BigInt> {
+        let transaction = (**transaction).clone();
+        let block_overrides = block_overrides.unwrap_or_default().try_cast()?;
+
+        let miner = self.miner.clone();
+
+        let gas_limit = self
+            .context
+            .runtime()
+            .spawn(async move {
+                let miner = miner.read().await;
+                miner.estimate_gas(transaction, block_overrides).await
+            })
+            .await
+            .unwrap()
+            .map_err(|e| napi::Error::new(Status::GenericFailure, e.to_string()))?;
+
+        Ok(BigInt::from(gas_limit))
+    }
+
+    #[doc = "Builds the \"pending\" block: the block that would result from mining right now, executed against a throwaway copy of the chain's state. Nothing is inserted into the blockchain, removed from the mempool, or otherwise committed."]
+    #[napi]
+    pub async fn pending_block(&self) -> napi::Result<PendingBlockResult> {
+        let miner = self.miner.clone();
+
+        let pending_block = self
+            .context
+            .runtime()
+            .spawn(async move {
+                let miner = miner.read().await;
+                miner.pending_block().await
+            })
+            .await
+            .unwrap()
+            .map_err(|e| napi::Error::new(Status::GenericFailure, e.to_string()))?;
+
+        Ok(PendingBlockResult::from(pending_block))
+    }
 }
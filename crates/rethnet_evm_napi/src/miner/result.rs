@@ -1,10 +1,11 @@
 use std::ops::Deref;
 
 use napi::{
-    bindgen_prelude::{Buffer, Either3},
-    Env,
+    bindgen_prelude::{BigInt, Buffer, Either3},
+    Env, Status,
 };
 use napi_derive::napi;
+use revm::db::StateRef;
 
 use crate::{
     block::Block,
@@ -13,6 +14,127 @@ use crate::{
     transaction::result::ExecutionResult,
 };
 
+/// An account's balance and nonce, as they stand in some particular state.
+#[napi(object)]
+pub struct Account {
+    /// The account's balance
+    pub balance: BigInt,
+    /// The account's nonce
+    pub nonce: BigInt,
+}
+
+/// The result of simulating a single transaction via `BlockMiner::simulate_transactions`.
+#[napi]
+pub struct TransactionSimulationResult {
+    result: revm::primitives::ExecutionResult,
+    trace: rethnet_evm::trace::Trace,
+}
+
+impl TransactionSimulationResult {
+    pub fn new(result: revm::primitives::ExecutionResult, trace: rethnet_evm::trace::Trace) -> Self {
+        Self { result, trace }
+    }
+}
+
+#[napi]
+impl TransactionSimulationResult {
+    #[doc = "Retrieves the transaction's execution result."]
+    #[napi(getter)]
+    pub fn result(&self, env: Env) -> napi::Result<ExecutionResult> {
+        ExecutionResult::new(&env, &self.result)
+    }
+
+    #[doc = "Retrieves the transaction's trace."]
+    #[napi(getter)]
+    pub fn trace(
+        &self,
+        env: Env,
+    ) -> napi::Result<Vec<Either3<TracingMessage, TracingStep, TracingMessageResult>>> {
+        self.trace
+            .messages
+            .iter()
+            .map(|message| match message {
+                rethnet_evm::trace::TraceMessage::Before(message) => {
+                    TracingMessage::new(&env, message).map(Either3::A)
+                }
+                rethnet_evm::trace::TraceMessage::Step(step) => {
+                    Ok(Either3::B(TracingStep::new(step)))
+                }
+                rethnet_evm::trace::TraceMessage::After(result) => ExecutionResult::new(&env, result)
+                    .map(|execution_result| Either3::C(TracingMessageResult { execution_result })),
+            })
+            .collect()
+    }
+}
+
+/// The `"pending"` block: the block that would result from mining right now, along with the
+/// receipts its transactions would produce.
+#[napi]
+pub struct PendingBlockResult {
+    inner: rethnet_evm::PendingBlock<rethnet_evm::state::StateError>,
+}
+
+impl From<rethnet_evm::PendingBlock<rethnet_evm::state::StateError>> for PendingBlockResult {
+    fn from(value: rethnet_evm::PendingBlock<rethnet_evm::state::StateError>) -> Self {
+        Self { inner: value }
+    }
+}
+
+#[napi]
+impl PendingBlockResult {
+    #[doc = "Retrieves the total gas used by the pending block's transactions."]
+    #[napi(getter)]
+    pub fn gas_used(&self) -> BigInt {
+        BigInt::from(self.inner.header().gas_used)
+    }
+
+    #[doc = "Retrieves the pending block's receipts root."]
+    #[napi(getter)]
+    pub fn receipts_root(&self) -> Buffer {
+        Buffer::from(self.inner.header().receipts_root.as_bytes())
+    }
+
+    #[doc = "Retrieves the state root that would result from mining the pending block."]
+    #[napi(getter)]
+    pub fn state_root(&self) -> Buffer {
+        Buffer::from(self.inner.header().state_root.as_bytes())
+    }
+
+    #[doc = "Retrieves the pending block's receipts."]
+    #[napi(getter)]
+    pub fn receipts(&self, env: Env) -> napi::Result<Vec<Receipt>> {
+        self.inner
+            .receipts()
+            .iter()
+            .map(|receipt| Receipt::new(&env, receipt))
+            .collect()
+    }
+
+    #[doc = "Retrieves the hash the pending block would have if it were mined right now."]
+    #[napi(getter)]
+    pub fn block_hash(&self) -> Buffer {
+        Buffer::from(self.inner.header().hash().as_bytes())
+    }
+
+    #[doc = "Retrieves the balance and nonce of the account at `address` in the pending block's \
+             state, or `null` if the account doesn't exist."]
+    #[napi]
+    pub fn account_by_address(&self, address: Buffer) -> napi::Result<Option<Account>> {
+        let address = rethnet_eth::Address::from_slice(&address);
+
+        self.inner
+            .state()
+            .basic(address)
+            .map_err(|e| napi::Error::new(Status::GenericFailure, e.to_string()))
+            .map(|account| {
+                account.map(|account| Account {
+                    balance: BigInt::from(account.balance),
+                    nonce: BigInt::from(account.nonce),
+                })
+            })
+    }
+}
+
 #[napi]
 pub struct MineBlockResult {
     inner: rethnet_evm::MineBlockResult,
@@ -97,4 +219,65 @@ impl MineBlockResult {
             })
             .collect()
     }
+
+    #[doc = "Retrieves the block's logs, flattened across transactions and assigned the \
+             block-global indices needed to serve `eth_getLogs`/subscription queries, alongside \
+             the aggregated logs bloom for the whole block."]
+    #[napi(getter)]
+    pub fn logs(&self) -> BlockLogs {
+        BlockLogs::from(self.inner.logs())
+    }
+}
+
+/// A single log as it appears within a mined block.
+#[napi(object)]
+pub struct Log {
+    /// The address that emitted the log
+    pub address: Buffer,
+    /// The topics of the log
+    pub topics: Vec<Buffer>,
+    /// The data of the log
+    pub data: Buffer,
+    /// The index of the transaction that emitted this log within the block
+    pub transaction_index: BigInt,
+    /// The log's index within the block, across all of the block's transactions
+    pub log_index: BigInt,
+    /// The log's index within the logs emitted by its own transaction
+    pub transaction_log_index: BigInt,
+}
+
+impl From<&rethnet_evm::BlockLog> for Log {
+    fn from(value: &rethnet_evm::BlockLog) -> Self {
+        Self {
+            address: Buffer::from(value.inner.address.as_bytes()),
+            topics: value
+                .inner
+                .topics
+                .iter()
+                .map(|topic| Buffer::from(topic.as_bytes()))
+                .collect(),
+            data: Buffer::from(value.inner.data.as_ref()),
+            transaction_index: BigInt::from(value.transaction_index as u64),
+            log_index: BigInt::from(value.log_index as u64),
+            transaction_log_index: BigInt::from(value.transaction_log_index as u64),
+        }
+    }
+}
+
+/// The block-global view of a mined block's logs, alongside its aggregated logs bloom.
+#[napi(object)]
+pub struct BlockLogs {
+    /// The block's logs, in the order they were emitted
+    pub logs: Vec<Log>,
+    /// The logs bloom obtained by aggregating every transaction's own logs bloom
+    pub logs_bloom: Buffer,
+}
+
+impl From<rethnet_evm::BlockLogs> for BlockLogs {
+    fn from(value: rethnet_evm::BlockLogs) -> Self {
+        Self {
+            logs: value.logs().iter().map(Log::from).collect(),
+            logs_bloom: Buffer::from(value.bloom().as_bytes()),
+        }
+    }
 }